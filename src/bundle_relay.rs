@@ -0,0 +1,226 @@
+use ethers::{
+    prelude::*,
+    types::{transaction::eip2718::TypedTransaction, Bytes, TransactionRequest, U64},
+    utils::keccak256,
+};
+use anyhow::{anyhow, Result};
+
+/// A single relay we can submit bundles to, e.g. Flashbots, bloXroute, or a
+/// private builder endpoint. Each relay gets its own HTTP client since the
+/// `X-Flashbots-Signature` header is per-request and per-relay.
+#[derive(Clone)]
+struct RelayEndpoint {
+    name: String,
+    url: String,
+    client: reqwest::Client,
+}
+
+/// Inclusion status reported back from a single relay after submitting a
+/// bundle. Relays don't guarantee inclusion, so this just reflects whether
+/// the relay accepted the bundle for the requested block.
+#[derive(Debug, Clone)]
+pub struct RelaySubmissionResult {
+    pub relay: String,
+    pub accepted: bool,
+    pub bundle_hash: Option<H256>,
+    pub error: Option<String>,
+}
+
+/// One leg of an atomic bundle. `Owned` legs are ours to sign with the
+/// executor's wallet; `Raw` legs are already-signed bytes lifted verbatim
+/// from the mempool (e.g. the victim's own tx in a sandwich) and must be
+/// relayed as-is, not re-signed.
+#[derive(Debug, Clone)]
+pub enum BundleLeg {
+    Owned(Box<TransactionRequest>),
+    Raw(Bytes),
+}
+
+/// Submits ordered, atomic bundles (front-run -> victim -> back-run) straight
+/// to private MEV relays over `eth_sendBundle`/`eth_callBundle`, instead of
+/// the public mempool where the legs would get unbundled and front-run
+/// themselves. Bundle payloads are signed with a dedicated searcher key.
+pub struct BundleRelay {
+    endpoints: Vec<RelayEndpoint>,
+    searcher_key: LocalWallet,
+}
+
+impl BundleRelay {
+    /// `submit_bundle` sends each bundle to every one of `relay_urls`;
+    /// `simulate_bundle` tries them in order until one succeeds.
+    /// `searcher_private_key` must be distinct from the wallet that signs
+    /// the bundle's transactions.
+    pub fn new(relay_urls: &[String], searcher_private_key: &str) -> Result<Self> {
+        if relay_urls.is_empty() {
+            return Err(anyhow!("BundleRelay requires at least one relay endpoint"));
+        }
+
+        let searcher_key: LocalWallet = searcher_private_key.parse()?;
+
+        let endpoints = relay_urls
+            .iter()
+            .map(|url| RelayEndpoint {
+                name: relay_name(url),
+                url: url.clone(),
+                client: reqwest::Client::new(),
+            })
+            .collect();
+
+        Ok(Self {
+            endpoints,
+            searcher_key,
+        })
+    }
+
+    /// Signs `Owned` legs with `wallet`, passes `Raw` legs through verbatim,
+    /// and submits the ordered bundle to every configured relay. Returns the
+    /// per-relay result rather than failing on the first rejection, plus the
+    /// hash of each leg so the caller can recognize them in the public mempool.
+    pub async fn submit_bundle(
+        &self,
+        legs: &[BundleLeg],
+        block_number: u64,
+        wallet: &LocalWallet,
+    ) -> Result<(Vec<H256>, Vec<RelaySubmissionResult>)> {
+        let signed_txs = self.sign_bundle_legs(legs, wallet).await?;
+        let tx_hashes = signed_txs.iter().map(|raw| H256::from(keccak256(raw))).collect();
+        let params = self.bundle_params(&signed_txs, block_number);
+
+        let mut results = Vec::with_capacity(self.endpoints.len());
+        for endpoint in &self.endpoints {
+            let result = self
+                .send_to_relay(endpoint, "eth_sendBundle", &params)
+                .await;
+            results.push(match result {
+                Ok(bundle_hash) => RelaySubmissionResult {
+                    relay: endpoint.name.clone(),
+                    accepted: true,
+                    bundle_hash,
+                    error: None,
+                },
+                Err(e) => RelaySubmissionResult {
+                    relay: endpoint.name.clone(),
+                    accepted: false,
+                    bundle_hash: None,
+                    error: Some(e.to_string()),
+                },
+            });
+        }
+
+        Ok((tx_hashes, results))
+    }
+
+    /// Dry-runs a bundle against the relay's simulated state (`eth_callBundle`)
+    /// without submitting it for inclusion. Used to sanity-check a bundle
+    /// before committing to a block.
+    pub async fn simulate_bundle(
+        &self,
+        legs: &[BundleLeg],
+        block_number: u64,
+        wallet: &LocalWallet,
+    ) -> Result<()> {
+        let signed_txs = self.sign_bundle_legs(legs, wallet).await?;
+        let params = self.bundle_params(&signed_txs, block_number);
+
+        // Simulation only needs to succeed against one relay; the first
+        // reachable one is enough to validate the bundle.
+        for endpoint in &self.endpoints {
+            if self
+                .send_to_relay(endpoint, "eth_callBundle", &params)
+                .await
+                .is_ok()
+            {
+                return Ok(());
+            }
+        }
+
+        Err(anyhow!("eth_callBundle failed on all configured relays"))
+    }
+
+    async fn sign_bundle_legs(
+        &self,
+        legs: &[BundleLeg],
+        wallet: &LocalWallet,
+    ) -> Result<Vec<Bytes>> {
+        let mut signed = Vec::with_capacity(legs.len());
+        for leg in legs {
+            match leg {
+                BundleLeg::Owned(tx) => {
+                    let typed_tx: TypedTransaction = (**tx).clone().into();
+                    let signature = wallet.sign_transaction(&typed_tx).await?;
+                    signed.push(typed_tx.rlp_signed(&signature));
+                }
+                BundleLeg::Raw(raw) => signed.push(raw.clone()),
+            }
+        }
+        Ok(signed)
+    }
+
+    fn bundle_params(&self, signed_txs: &[Bytes], block_number: u64) -> serde_json::Value {
+        serde_json::json!([{
+            "txs": signed_txs.iter().map(|tx| format!("0x{}", hex::encode(tx))).collect::<Vec<_>>(),
+            "blockNumber": format!("0x{:x}", U64::from(block_number)),
+        }])
+    }
+
+    async fn send_to_relay(
+        &self,
+        endpoint: &RelayEndpoint,
+        method: &str,
+        params: &serde_json::Value,
+    ) -> Result<Option<H256>> {
+        let body = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        });
+
+        let signature_header = self.sign_payload(&body).await?;
+
+        let response = endpoint
+            .client
+            .post(&endpoint.url)
+            .header("Content-Type", "application/json")
+            .header("X-Flashbots-Signature", signature_header)
+            .json(&body)
+            .send()
+            .await?;
+
+        let json: serde_json::Value = response.json().await?;
+
+        if let Some(error) = json.get("error") {
+            return Err(anyhow!("relay {} rejected bundle: {}", endpoint.name, error));
+        }
+
+        let bundle_hash = json
+            .get("result")
+            .and_then(|r| r.get("bundleHash"))
+            .and_then(|h| h.as_str())
+            .and_then(|h| h.parse::<H256>().ok());
+
+        Ok(bundle_hash)
+    }
+
+    /// Relays require request bodies to be signed by the searcher's identity
+    /// key, in the form `<address>:<signature>`, where the signature is over
+    /// the hex-encoded keccak256 hash of the JSON body.
+    async fn sign_payload(&self, body: &serde_json::Value) -> Result<String> {
+        let body_bytes = serde_json::to_vec(body)?;
+        let digest = format!("0x{}", hex::encode(keccak256(&body_bytes)));
+        let signature = self
+            .searcher_key
+            .sign_message(digest.as_bytes())
+            .await?;
+        Ok(format!("{:?}:0x{}", self.searcher_key.address(), signature))
+    }
+}
+
+fn relay_name(url: &str) -> String {
+    url.trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .split('/')
+        .next()
+        .unwrap_or(url)
+        .to_string()
+}
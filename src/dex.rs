@@ -0,0 +1,369 @@
+use ethers::{
+    abi::{decode, encode, ParamType, Token},
+    providers::Middleware,
+    types::{Address, Bytes, TransactionRequest, U256},
+};
+use anyhow::{anyhow, Result};
+
+/// The Uniswap V2 factory used to look up pair reserves for sizing a
+/// sandwich. SushiSwap and other V2 forks share the same pair/factory ABI,
+/// so only the factory address would need to change to support them.
+const UNISWAP_V2_FACTORY: &str = "0x5C69bEe701ef814a2B6a3EDD4B1652CB9cc5aA6f";
+
+mod selectors {
+    pub const SWAP_EXACT_TOKENS_FOR_TOKENS: [u8; 4] = [0x38, 0xed, 0x17, 0x39];
+    pub const SWAP_EXACT_ETH_FOR_TOKENS: [u8; 4] = [0x7f, 0xf3, 0x6a, 0xb5];
+    pub const SWAP_EXACT_TOKENS_FOR_ETH: [u8; 4] = [0x18, 0xcb, 0xaf, 0xe5];
+    pub const SWAP_EXACT_TOKENS_FOR_TOKENS_FEE: [u8; 4] = [0x5c, 0x11, 0xd7, 0x95];
+    pub const SWAP_EXACT_ETH_FOR_TOKENS_FEE: [u8; 4] = [0xb6, 0xf9, 0xde, 0x95];
+    pub const SWAP_EXACT_TOKENS_FOR_ETH_FEE: [u8; 4] = [0x79, 0x1a, 0xc9, 0x47];
+    pub const GET_PAIR: [u8; 4] = [0xe6, 0xa4, 0x39, 0x05];
+    pub const GET_RESERVES: [u8; 4] = [0x09, 0x02, 0xf1, 0xac];
+}
+
+/// Router contracts this bot knows how to decode swaps for. Uniswap V3
+/// isn't listed here: its router doesn't use the V2-style selectors
+/// `decode_swap_calldata` recognizes, so listing it would just mean every
+/// V3 swap silently falls through to `Ok(None)`.
+const KNOWN_ROUTERS: [&str; 2] = [
+    "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D", // Uniswap V2
+    "0xd9e1cE17f2641f24aE83637ab66a2cca9C378B9F", // SushiSwap
+];
+
+/// Whether `address` is a known DEX router contract worth decoding swaps for.
+pub fn is_known_dex_router(address: Address) -> bool {
+    KNOWN_ROUTERS
+        .iter()
+        .any(|router| address == router.parse::<Address>().unwrap())
+}
+
+/// A decoded Uniswap V2 router swap call, normalized across the
+/// token/token, ETH-in, and fee-on-transfer variants the router exposes.
+#[derive(Debug, Clone)]
+pub struct DecodedSwap {
+    pub amount_in: U256,
+    pub amount_out_min: U256,
+    pub path: Vec<Address>,
+}
+
+/// Decodes `data` as a call to one of the common Uniswap V2 router swap
+/// functions. `value` is the transaction's ETH value, used as `amountIn`
+/// for the ETH-in variants where it isn't a calldata parameter. Returns
+/// `None` for selectors this bot doesn't recognize.
+pub fn decode_swap_calldata(data: &Bytes, value: U256) -> Result<Option<DecodedSwap>> {
+    if data.0.len() < 4 {
+        return Ok(None);
+    }
+
+    let selector: [u8; 4] = data.0[0..4].try_into().unwrap();
+    let params = &data.0[4..];
+
+    use selectors::*;
+
+    let swap = match selector {
+        SWAP_EXACT_TOKENS_FOR_TOKENS | SWAP_EXACT_TOKENS_FOR_TOKENS_FEE
+        | SWAP_EXACT_TOKENS_FOR_ETH | SWAP_EXACT_TOKENS_FOR_ETH_FEE => {
+            let tokens = decode(
+                &[
+                    ParamType::Uint(256),
+                    ParamType::Uint(256),
+                    ParamType::Array(Box::new(ParamType::Address)),
+                    ParamType::Address,
+                    ParamType::Uint(256),
+                ],
+                params,
+            )?;
+            DecodedSwap {
+                amount_in: as_uint(&tokens[0])?,
+                amount_out_min: as_uint(&tokens[1])?,
+                path: as_path(&tokens[2])?,
+            }
+        }
+        SWAP_EXACT_ETH_FOR_TOKENS | SWAP_EXACT_ETH_FOR_TOKENS_FEE => {
+            let tokens = decode(
+                &[
+                    ParamType::Uint(256),
+                    ParamType::Array(Box::new(ParamType::Address)),
+                    ParamType::Address,
+                    ParamType::Uint(256),
+                ],
+                params,
+            )?;
+            DecodedSwap {
+                amount_in: value,
+                amount_out_min: as_uint(&tokens[0])?,
+                path: as_path(&tokens[1])?,
+            }
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(swap))
+}
+
+fn as_uint(token: &Token) -> Result<U256> {
+    token.clone().into_uint().ok_or_else(|| anyhow!("expected an ABI uint token"))
+}
+
+fn as_path(token: &Token) -> Result<Vec<Address>> {
+    token
+        .clone()
+        .into_array()
+        .ok_or_else(|| anyhow!("expected an ABI address[] token"))?
+        .into_iter()
+        .map(|t| t.into_address().ok_or_else(|| anyhow!("expected an ABI address token")))
+        .collect()
+}
+
+/// Looks up the live reserves of the `token_a`/`token_b` pair, ordered to
+/// match the caller's (token_a, token_b) argument order rather than the
+/// pool's internal token0/token1 order. Returns `None` if no pair exists.
+pub async fn get_reserves<M: Middleware>(
+    provider: &M,
+    token_a: Address,
+    token_b: Address,
+) -> Result<Option<(U256, U256)>> {
+    let factory: Address = UNISWAP_V2_FACTORY.parse()?;
+
+    let pair = get_pair(provider, factory, token_a, token_b).await?;
+    if pair == Address::zero() {
+        return Ok(None);
+    }
+
+    let (reserve0, reserve1) = get_pair_reserves(provider, pair).await?;
+
+    // Uniswap V2 orders a pair's reserves by the numerically smaller token
+    // address (token0), which may not match the order the caller asked for.
+    Ok(Some(if token_a < token_b {
+        (reserve0, reserve1)
+    } else {
+        (reserve1, reserve0)
+    }))
+}
+
+async fn get_pair<M: Middleware>(
+    provider: &M,
+    factory: Address,
+    token_a: Address,
+    token_b: Address,
+) -> Result<Address> {
+    let mut data = selectors::GET_PAIR.to_vec();
+    data.extend(encode(&[Token::Address(token_a), Token::Address(token_b)]));
+
+    let tx = TransactionRequest::new().to(factory).data(data).into();
+    let result = provider
+        .call(&tx, None)
+        .await
+        .map_err(|e| anyhow!("factory.getPair call failed: {e}"))?;
+
+    let tokens = decode(&[ParamType::Address], &result)?;
+    tokens[0]
+        .clone()
+        .into_address()
+        .ok_or_else(|| anyhow!("malformed getPair response"))
+}
+
+async fn get_pair_reserves<M: Middleware>(provider: &M, pair: Address) -> Result<(U256, U256)> {
+    let tx = TransactionRequest::new().to(pair).data(selectors::GET_RESERVES.to_vec()).into();
+    let result = provider
+        .call(&tx, None)
+        .await
+        .map_err(|e| anyhow!("pair.getReserves call failed: {e}"))?;
+
+    let tokens = decode(
+        &[ParamType::Uint(112), ParamType::Uint(112), ParamType::Uint(32)],
+        &result,
+    )?;
+
+    Ok((as_uint(&tokens[0])?, as_uint(&tokens[1])?))
+}
+
+/// The output side of the constant-product formula with Uniswap's 0.3% fee:
+/// `out = (y * 997 * in) / (x * 1000 + 997 * in)`.
+pub fn amount_out(amount_in: U256, reserve_in: U256, reserve_out: U256) -> Option<U256> {
+    if amount_in.is_zero() || reserve_in.is_zero() || reserve_out.is_zero() {
+        return None;
+    }
+
+    let amount_in_with_fee = amount_in.checked_mul(U256::from(997))?;
+    let numerator = amount_in_with_fee.checked_mul(reserve_out)?;
+    let denominator = reserve_in.checked_mul(U256::from(1000))?.checked_add(amount_in_with_fee)?;
+
+    Some(numerator / denominator)
+}
+
+/// A sized front-run: how much of the input token to buy first, and how
+/// much of the output token that buy yields (which the back-run then sells
+/// back in its entirety).
+#[derive(Debug, Clone, Copy)]
+pub struct SandwichPlan {
+    pub front_run_amount_in: U256,
+    pub front_run_amount_out: U256,
+}
+
+/// Computes the profit-maximizing front-run size against a victim swap.
+///
+/// The victim's `amount_out_min` is the binding constraint: we buy amount
+/// `f` first (moving reserves to `x + f`, `y - out(f)`), then the victim
+/// buys against the moved reserves, and `f` is the largest amount such that
+/// the victim's realized output still equals `amount_out_min` exactly.
+/// Substituting the victim's swap on the post-front-run reserves and
+/// setting victim output = `amount_out_min` yields a quadratic in `f`;
+/// we solve it in floating point (the bot's own `Simulator` is the source
+/// of truth on realized profit, so this only needs to be a good candidate)
+/// and round back down to the largest valid integer root.
+pub fn plan_sandwich(reserve_in: U256, reserve_out: U256, victim: &DecodedSwap) -> Option<SandwichPlan> {
+    if victim.amount_out_min.is_zero() {
+        // No slippage protection means nothing binds how large the victim's
+        // trade can be pushed against, i.e. no constraint to optimize for.
+        return None;
+    }
+    if reserve_in.is_zero() || reserve_out.is_zero() {
+        return None;
+    }
+
+    let f = optimal_frontrun_amount(reserve_in, reserve_out, victim.amount_in, victim.amount_out_min)?;
+    if f.is_zero() {
+        return None;
+    }
+
+    let out = amount_out(f, reserve_in, reserve_out)?;
+    if out.is_zero() {
+        return None;
+    }
+
+    Some(SandwichPlan {
+        front_run_amount_in: f,
+        front_run_amount_out: out,
+    })
+}
+
+fn optimal_frontrun_amount(
+    reserve_in: U256,
+    reserve_out: U256,
+    victim_amount_in: U256,
+    victim_amount_out_min: U256,
+) -> Option<U256> {
+    let x = u256_to_f64(reserve_in);
+    let y = u256_to_f64(reserve_out);
+    let a = u256_to_f64(victim_amount_in);
+    let m = u256_to_f64(victim_amount_out_min);
+
+    // 997000*M*f^2 + M*(1997000x + 994009A)*f + M*(1000000x^2 + 997000xA) - 997000xyA = 0
+    let qa = 997_000.0 * m;
+    let qb = m * (1_997_000.0 * x + 994_009.0 * a);
+    let qc = m * (1_000_000.0 * x * x + 997_000.0 * x * a) - 997_000.0 * x * y * a;
+
+    if qa == 0.0 {
+        return None;
+    }
+
+    let discriminant = qb * qb - 4.0 * qa * qc;
+    if discriminant < 0.0 {
+        // No real root: the victim's slippage tolerance can't be pushed to
+        // its limit by any front-run size, so there's nothing to extract.
+        return None;
+    }
+
+    let f = (-qb + discriminant.sqrt()) / (2.0 * qa);
+    if !f.is_finite() || f <= 0.0 {
+        return None;
+    }
+
+    f64_to_u256(f.floor())
+}
+
+fn u256_to_f64(value: U256) -> f64 {
+    value.to_string().parse().unwrap_or(f64::MAX)
+}
+
+fn f64_to_u256(value: f64) -> Option<U256> {
+    if value < 0.0 || !value.is_finite() {
+        return None;
+    }
+    U256::from_dec_str(&format!("{:.0}", value)).ok()
+}
+
+/// Encodes a call to `swapExactTokensForTokens(amountIn, amountOutMin, path, to, deadline)`.
+pub fn encode_swap_exact_tokens_for_tokens(
+    amount_in: U256,
+    amount_out_min: U256,
+    path: &[Address],
+    to: Address,
+    deadline: U256,
+) -> Bytes {
+    let params = encode(&[
+        Token::Uint(amount_in),
+        Token::Uint(amount_out_min),
+        Token::Array(path.iter().map(|a| Token::Address(*a)).collect()),
+        Token::Address(to),
+        Token::Uint(deadline),
+    ]);
+
+    let mut data = selectors::SWAP_EXACT_TOKENS_FOR_TOKENS.to_vec();
+    data.extend(params);
+    Bytes::from(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amount_out_applies_the_997_1000_fee() {
+        let out = amount_out(U256::from(1_000), U256::from(1_000_000), U256::from(1_000_000)).unwrap();
+        // out = (997*1000*1_000_000) / (1_000_000*1000 + 997*1000) = 996
+        assert_eq!(out, U256::from(996));
+    }
+
+    #[test]
+    fn amount_out_rejects_empty_reserves() {
+        assert!(amount_out(U256::from(1_000), U256::zero(), U256::from(1_000_000)).is_none());
+        assert!(amount_out(U256::zero(), U256::from(1_000_000), U256::from(1_000_000)).is_none());
+    }
+
+    #[test]
+    fn plan_sandwich_sizes_a_frontrun_against_slippage() {
+        let victim = DecodedSwap {
+            amount_in: U256::from(1_000u64),
+            amount_out_min: U256::from(900u64),
+            path: vec![Address::zero(), Address::repeat_byte(1)],
+        };
+        let plan = plan_sandwich(U256::from(1_000_000u64), U256::from(1_000_000u64), &victim).unwrap();
+        assert!(plan.front_run_amount_in > U256::zero());
+        assert!(plan.front_run_amount_out > U256::zero());
+    }
+
+    #[test]
+    fn plan_sandwich_is_none_without_slippage_protection() {
+        let victim = DecodedSwap {
+            amount_in: U256::from(1_000u64),
+            amount_out_min: U256::zero(),
+            path: vec![Address::zero(), Address::repeat_byte(1)],
+        };
+        assert!(plan_sandwich(U256::from(1_000_000u64), U256::from(1_000_000u64), &victim).is_none());
+    }
+
+    #[test]
+    fn decode_swap_calldata_reads_swap_exact_tokens_for_tokens() {
+        let path = vec![Address::repeat_byte(1), Address::repeat_byte(2)];
+        let data = encode_swap_exact_tokens_for_tokens(
+            U256::from(1_000u64),
+            U256::from(900u64),
+            &path,
+            Address::repeat_byte(3),
+            U256::from(123_456u64),
+        );
+
+        let swap = decode_swap_calldata(&data, U256::zero()).unwrap().unwrap();
+        assert_eq!(swap.amount_in, U256::from(1_000u64));
+        assert_eq!(swap.amount_out_min, U256::from(900u64));
+        assert_eq!(swap.path, path);
+    }
+
+    #[test]
+    fn decode_swap_calldata_ignores_unknown_selectors() {
+        let data = Bytes::from(vec![0xde, 0xad, 0xbe, 0xef]);
+        assert!(decode_swap_calldata(&data, U256::zero()).unwrap().is_none());
+    }
+}
@@ -1,5 +1,6 @@
 use ethers::{
     prelude::*,
+    middleware::gas_oracle::{GasOracle, GasOracleMiddleware, ProviderOracle},
     providers::{Provider, Ws},
     types::{Address, U256, H256},
 };
@@ -7,13 +8,41 @@ use std::{collections::HashMap, sync::Arc, time::Duration};
 use tokio::time::sleep;
 use anyhow::Result;
 
+mod bundle_relay;
+mod dex;
+mod mempool;
+mod simulator;
+mod strategy;
+mod strategies;
+
+use bundle_relay::{BundleLeg, BundleRelay, RelaySubmissionResult};
+use mempool::MempoolMonitor;
+use simulator::Simulator;
+use strategy::{Strategy, TxContext};
+use strategies::{ArbitrageStrategy, LiquidationStrategy, SandwichStrategy};
+
+// signer -> 本地nonce管理 -> 可插拔的gas oracle，泛型P让IPC/HTTP也能代替Ws
+pub type ExecutorMiddleware<P = Ws> =
+    SignerMiddleware<NonceManagerMiddleware<GasOracleMiddleware<Provider<P>, Box<dyn GasOracle>>>, LocalWallet>;
+
+fn build_middleware_stack<P: JsonRpcClient + Clone + 'static>(
+    provider: Provider<P>,
+    wallet: LocalWallet,
+    gas_oracle: Box<dyn GasOracle>,
+) -> ExecutorMiddleware<P> {
+    let address = wallet.address();
+    let gas_middleware = GasOracleMiddleware::new(provider, gas_oracle);
+    let nonce_middleware = NonceManagerMiddleware::new(gas_middleware, address);
+    SignerMiddleware::new(nonce_middleware, wallet)
+}
+
 #[derive(Debug, Clone)]
 pub struct MEVOpportunity {
     pub opportunity_type: OpportunityType,
     pub profit_estimate: U256,
     pub gas_estimate: U256,
     pub block_number: u64,
-    pub transaction_data: Vec<TransactionRequest>,
+    pub transaction_data: Vec<BundleLeg>,
 }
 
 #[derive(Debug, Clone)]
@@ -38,10 +67,10 @@ pub enum OpportunityType {
 
 pub struct MEVBot {
     provider: Arc<Provider<Ws>>,
-    wallet: LocalWallet,
     mempool_monitor: MempoolMonitor,
-    opportunity_finder: OpportunityFinder,
-    executor: TransactionExecutor,
+    executor: Arc<TransactionExecutor>,
+    // 可插拔的策略列表：每种机会类型都是独立实现，新增品类只需要新增一个Strategy
+    strategies: Vec<Box<dyn Strategy>>,
     min_profit_threshold: U256,
 }
 
@@ -49,20 +78,36 @@ impl MEVBot {
     pub async fn new(
         ws_url: &str,
         private_key: &str,
+        relay_urls: &[String],
+        searcher_private_key: &str,
         min_profit_wei: U256,
     ) -> Result<Self> {
         let provider = Provider::<Ws>::connect(ws_url).await?;
         let provider = Arc::new(provider);
-        
+
         let wallet: LocalWallet = private_key.parse()?;
         let wallet = wallet.with_chain_id(1u64); // Mainnet
-        
+
+        let gas_oracle: Box<dyn GasOracle> = Box::new(ProviderOracle::new((*provider).clone()));
+        let client = Arc::new(build_middleware_stack((*provider).clone(), wallet, gas_oracle));
+
+        let relay = BundleRelay::new(relay_urls, searcher_private_key)?;
+
+        let opportunity_finder = Arc::new(OpportunityFinder::new(provider.clone()));
+        let simulator = Arc::new(Simulator::new(client.clone()));
+        let executor = Arc::new(TransactionExecutor::with_relay(client, relay));
+
+        let strategies: Vec<Box<dyn Strategy>> = vec![
+            Box::new(SandwichStrategy::new(provider.clone(), simulator, executor.clone())),
+            Box::new(LiquidationStrategy),
+            Box::new(ArbitrageStrategy::new(opportunity_finder)),
+        ];
+
         Ok(Self {
-            provider: provider.clone(),
-            wallet,
-            mempool_monitor: MempoolMonitor::new(provider.clone()),
-            opportunity_finder: OpportunityFinder::new(provider.clone()),
-            executor: TransactionExecutor::new(provider.clone()),
+            provider,
+            mempool_monitor: MempoolMonitor::new(),
+            executor,
+            strategies,
             min_profit_threshold: min_profit_wei,
         })
     }
@@ -82,161 +127,84 @@ impl MEVBot {
     }
 
     async fn start_mempool_monitoring(&self) -> Result<()> {
-        let mut stream = self.provider.subscribe_pending_txs().await?;
-        
-        while let Some(tx_hash) = stream.next().await {
-            match self.provider.get_transaction(tx_hash).await? {
-                Some(tx) => {
-                    if let Some(opportunity) = self.analyze_transaction(&tx).await? {
-                        if opportunity.profit_estimate > self.min_profit_threshold {
-                            tracing::info!("Found MEV opportunity: {:?}", opportunity);
-                            
-                            if let Err(e) = self.executor.execute_opportunity(&opportunity, &self.wallet).await {
-                                tracing::error!("Failed to execute opportunity: {}", e);
-                            }
-                        }
+        let stream = self.provider.subscribe_pending_txs().await?;
+
+        // 多个pending tx并发拉取和分析，避免卡在单个慢get_transaction上丢失机会
+        stream
+            .for_each_concurrent(self.mempool_monitor.concurrency(), |tx_hash| async move {
+                // 去重 + 排除自己提交的交易，二者都在分析前过滤掉
+                if !self.mempool_monitor.should_process(tx_hash) {
+                    return;
+                }
+
+                let tx = match self.provider.get_transaction(tx_hash).await {
+                    Ok(Some(tx)) => tx,
+                    Ok(None) => return,
+                    Err(e) => {
+                        tracing::error!("Failed to fetch transaction {tx_hash:?}: {e}");
+                        return;
                     }
+                };
+
+                if let Err(e) = self.dispatch(TxContext::PendingTransaction(Box::new(tx))).await {
+                    tracing::error!("Failed to dispatch pending tx {tx_hash:?} to strategies: {e}");
                 }
-                None => continue,
-            }
-        }
-        
+            })
+            .await;
+
         Ok(())
     }
 
     async fn start_block_monitoring(&self) -> Result<()> {
         let mut stream = self.provider.subscribe_blocks().await?;
-        
+
         while let Some(block) = stream.next().await {
             tracing::info!("New block: {}", block.number.unwrap());
-            
-            // 分析新区块中的机会
-            if let Some(opportunities) = self.opportunity_finder.find_block_opportunities(&block).await? {
-                for opportunity in opportunities {
-                    if opportunity.profit_estimate > self.min_profit_threshold {
-                        if let Err(e) = self.executor.execute_opportunity(&opportunity, &self.wallet).await {
-                            tracing::error!("Failed to execute block opportunity: {}", e);
-                        }
-                    }
-                }
-            }
+
+            self.dispatch(TxContext::Block(Box::new(block))).await?;
         }
-        
+
         Ok(())
     }
 
     async fn start_arbitrage_monitoring(&self) -> Result<()> {
         loop {
-            // 检查不同DEX之间的价格差异
-            if let Some(opportunities) = self.opportunity_finder.find_arbitrage_opportunities().await? {
-                for opportunity in opportunities {
-                    if opportunity.profit_estimate > self.min_profit_threshold {
-                        tracing::info!("Found arbitrage opportunity: {:?}", opportunity);
-                        
-                        if let Err(e) = self.executor.execute_opportunity(&opportunity, &self.wallet).await {
-                            tracing::error!("Failed to execute arbitrage: {}", e);
-                        }
-                    }
-                }
-            }
-            
-            sleep(Duration::from_millis(100)).await;
-        }
-    }
+            self.dispatch(TxContext::Tick).await?;
 
-    async fn analyze_transaction(&self, tx: &Transaction) -> Result<Option<MEVOpportunity>> {
-        // 分析交易是否可以被夹子攻击
-        if let Some(sandwich_opportunity) = self.check_sandwich_opportunity(tx).await? {
-            return Ok(Some(sandwich_opportunity));
-        }
-        
-        // 检查是否有清算机会
-        if let Some(liquidation_opportunity) = self.check_liquidation_opportunity(tx).await? {
-            return Ok(Some(liquidation_opportunity));
+            sleep(Duration::from_millis(100)).await;
         }
-        
-        Ok(None)
     }
 
-    async fn check_sandwich_opportunity(&self, tx: &Transaction) -> Result<Option<MEVOpportunity>> {
-        // 检查是否是DEX交易
-        if let Some(to) = tx.to {
-            // 这里需要实现具体的夹子攻击检测逻辑
-            // 1. 检查是否是Uniswap/SushiSwap等DEX的交易
-            // 2. 解析交易数据，获取交易对和金额
-            // 3. 计算潜在利润
-            
-            // 简化实现
-            if self.is_dex_swap(&to) {
-                // 解析swap参数
-                if let Some((token, amount)) = self.parse_swap_data(&tx.input)? {
-                    let profit = self.calculate_sandwich_profit(token, amount).await?;
-                    
-                    if profit > U256::zero() {
-                        return Ok(Some(MEVOpportunity {
-                            opportunity_type: OpportunityType::Sandwich {
-                                target_tx: tx.hash,
-                                token,
-                                amount,
-                            },
-                            profit_estimate: profit,
-                            gas_estimate: U256::from(500000),
-                            block_number: tx.block_number.unwrap_or_default().as_u64(),
-                            transaction_data: self.build_sandwich_transactions(token, amount)?,
-                        }));
-                    }
+    // 把ctx分发给每个策略，执行利润超过min_profit_threshold的机会
+    async fn dispatch(&self, ctx: TxContext) -> Result<()> {
+        for strategy in &self.strategies {
+            for mut opportunity in strategy.evaluate(&ctx).await? {
+                if opportunity.profit_estimate <= self.min_profit_threshold {
+                    continue;
                 }
-            }
-        }
-        
-        Ok(None)
-    }
 
-    async fn check_liquidation_opportunity(&self, tx: &Transaction) -> Result<Option<MEVOpportunity>> {
-        // 检查是否有清算机会
-        // 这需要监控各种DeFi协议的健康度
-        Ok(None)
-    }
+                tracing::info!("[{}] found MEV opportunity: {:?}", strategy.name(), opportunity);
 
-    fn is_dex_swap(&self, address: &Address) -> bool {
-        // 检查是否是知名DEX的路由合约
-        let dex_routers = vec![
-            "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D", // Uniswap V2
-            "0xE592427A0AEce92De3Edee1F18E0157C05861564", // Uniswap V3
-            "0xd9e1cE17f2641f24aE83637ab66a2cca9C378B9F", // SushiSwap
-        ];
-        
-        dex_routers.iter().any(|router| address == &router.parse::<Address>().unwrap())
-    }
-
-    fn parse_swap_data(&self, data: &Bytes) -> Result<Option<(Address, U256)>> {
-        // 解析交易数据，提取token地址和金额
-        // 这需要根据不同的函数签名来解析
-        Ok(None)
-    }
-
-    async fn calculate_sandwich_profit(&self, token: Address, amount: U256) -> Result<U256> {
-        // 计算夹子攻击的潜在利润
-        // 这需要模拟交易的影响
-        Ok(U256::zero())
-    }
+                opportunity.transaction_data = strategy.build(&opportunity).await?;
 
-    fn build_sandwich_transactions(&self, token: Address, amount: U256) -> Result<Vec<TransactionRequest>> {
-        // 构建前后夹子交易
-        Ok(vec![])
-    }
-}
-
-pub struct MempoolMonitor {
-    provider: Arc<Provider<Ws>>,
-}
+                match self.executor.execute_opportunity(&opportunity).await {
+                    Ok(submitted) => self.mempool_monitor.exclude_own(&submitted),
+                    Err(e) => tracing::error!(
+                        "[{}] failed to execute opportunity: {}",
+                        strategy.name(),
+                        e
+                    ),
+                }
+            }
+        }
 
-impl MempoolMonitor {
-    pub fn new(provider: Arc<Provider<Ws>>) -> Self {
-        Self { provider }
+        Ok(())
     }
 }
 
+// provider/dex_contracts aren't read yet: find_block_opportunities and
+// find_arbitrage_opportunities are stubs, same as LiquidationStrategy
+#[allow(dead_code)]
 pub struct OpportunityFinder {
     provider: Arc<Provider<Ws>>,
     dex_contracts: HashMap<String, Address>,
@@ -254,7 +222,7 @@ impl OpportunityFinder {
         }
     }
 
-    pub async fn find_block_opportunities(&self, block: &Block<H256>) -> Result<Option<Vec<MEVOpportunity>>> {
+    pub async fn find_block_opportunities(&self, _block: &Block<H256>) -> Result<Option<Vec<MEVOpportunity>>> {
         // 分析区块中的交易，寻找MEV机会
         Ok(None)
     }
@@ -265,60 +233,134 @@ impl OpportunityFinder {
     }
 }
 
-pub struct TransactionExecutor {
-    provider: Arc<Provider<Ws>>,
+pub struct TransactionExecutor<P: JsonRpcClient = Ws> {
+    client: Arc<ExecutorMiddleware<P>>,
+    // 夹子/套利交易必须走私有中继，否则front-run/back-run会在公共内存池里被拆开
+    relay: Option<BundleRelay>,
 }
 
-impl TransactionExecutor {
-    pub fn new(provider: Arc<Provider<Ws>>) -> Self {
-        Self { provider }
+impl<P> TransactionExecutor<P>
+where
+    P: JsonRpcClient + 'static,
+{
+    pub fn new(client: Arc<ExecutorMiddleware<P>>) -> Self {
+        Self {
+            client,
+            relay: None,
+        }
+    }
+
+    // 和new一样，但夹子/套利bundle会走relay而不是逐笔广播
+    pub fn with_relay(client: Arc<ExecutorMiddleware<P>>, relay: BundleRelay) -> Self {
+        Self {
+            client,
+            relay: Some(relay),
+        }
+    }
+
+    // 签名/提交交易所用的地址，也是夹子/套利的受益地址
+    pub fn address(&self) -> Address {
+        self.client.signer().address()
     }
 
-    pub async fn execute_opportunity(
-        &self,
-        opportunity: &MEVOpportunity,
-        wallet: &LocalWallet,
-    ) -> Result<()> {
+    // 返回实际发出的交易hash，供调用方把自己的bundle排除在mempool分析之外
+    pub async fn execute_opportunity(&self, opportunity: &MEVOpportunity) -> Result<Vec<H256>> {
         tracing::info!("Executing MEV opportunity: {:?}", opportunity.opportunity_type);
-        
+
         match &opportunity.opportunity_type {
-            OpportunityType::Arbitrage { .. } => {
-                self.execute_arbitrage(opportunity, wallet).await?;
-            }
-            OpportunityType::Liquidation { .. } => {
-                self.execute_liquidation(opportunity, wallet).await?;
-            }
-            OpportunityType::Sandwich { .. } => {
-                self.execute_sandwich(opportunity, wallet).await?;
-            }
+            OpportunityType::Arbitrage { .. } => self.execute_arbitrage(opportunity).await,
+            OpportunityType::Liquidation { .. } => self.execute_liquidation(opportunity).await,
+            OpportunityType::Sandwich { .. } => self.execute_sandwich(opportunity).await,
         }
-        
-        Ok(())
     }
 
-    async fn execute_arbitrage(&self, opportunity: &MEVOpportunity, wallet: &LocalWallet) -> Result<()> {
-        // 执行套利交易
-        Ok(())
+    async fn execute_arbitrage(&self, opportunity: &MEVOpportunity) -> Result<Vec<H256>> {
+        // 套利的前后两笔交易也必须原子提交，否则中间可能被插队
+        self.submit_as_bundle(opportunity).await
     }
 
-    async fn execute_liquidation(&self, opportunity: &MEVOpportunity, wallet: &LocalWallet) -> Result<()> {
-        // 执行清算交易
-        Ok(())
+    async fn execute_liquidation(&self, opportunity: &MEVOpportunity) -> Result<Vec<H256>> {
+        // 清算不需要抢同一笔交易的顺序，直接通过节点发送即可；
+        // nonce manager保证它不会和正在走bundle的夹子/套利腿抢占同一个nonce
+        let mut hashes = Vec::with_capacity(opportunity.transaction_data.len());
+        for leg in &opportunity.transaction_data {
+            let hash = match leg {
+                BundleLeg::Owned(tx) => {
+                    let pending = self.client.send_transaction((**tx).clone(), None).await?;
+                    let hash = *pending;
+                    pending.await?;
+                    hash
+                }
+                // 清算流程自己不产生已签名的外部交易，这里只是为了与BundleLeg类型保持一致
+                BundleLeg::Raw(raw) => {
+                    let pending = self.client.provider().send_raw_transaction(raw.clone()).await?;
+                    let hash = *pending;
+                    pending.await?;
+                    hash
+                }
+            };
+            hashes.push(hash);
+        }
+        Ok(hashes)
     }
 
-    async fn execute_sandwich(&self, opportunity: &MEVOpportunity, wallet: &LocalWallet) -> Result<()> {
-        // 执行夹子攻击
-        Ok(())
+    async fn execute_sandwich(&self, opportunity: &MEVOpportunity) -> Result<Vec<H256>> {
+        // front-run -> victim -> back-run 必须作为一个原子bundle提交
+        self.submit_as_bundle(opportunity).await
+    }
+
+    // 原子bundle提交，relay未配置时不回退到逐笔广播，直接no-op
+    async fn submit_as_bundle(&self, opportunity: &MEVOpportunity) -> Result<Vec<H256>> {
+        let Some(relay) = &self.relay else {
+            tracing::warn!("no relay configured, skipping bundle submission");
+            return Ok(vec![]);
+        };
+
+        // bundle腿由钱包直接签名、不经过SignerMiddleware，这里从nonce manager按顺序给未设nonce的自有腿分配nonce
+        let nonce_manager = self.client.inner();
+        nonce_manager.initialize_nonce(None).await?;
+        let mut legs = opportunity.transaction_data.clone();
+        for leg in &mut legs {
+            if let BundleLeg::Owned(tx) = leg {
+                if tx.nonce.is_none() {
+                    tx.nonce = Some(nonce_manager.next());
+                }
+            }
+        }
+
+        let (tx_hashes, results) = relay
+            .submit_bundle(
+                &legs,
+                opportunity.block_number,
+                self.client.signer(),
+            )
+            .await?;
+
+        log_relay_results(&results);
+
+        Ok(tx_hashes)
+    }
+}
+
+fn log_relay_results(results: &[RelaySubmissionResult]) {
+    for result in results {
+        if result.accepted {
+            tracing::info!("relay {} accepted bundle: {:?}", result.relay, result.bundle_hash);
+        } else {
+            tracing::warn!("relay {} rejected bundle: {:?}", result.relay, result.error);
+        }
     }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    tracing_subscriber::init();
+    tracing_subscriber::fmt::init();
 
     let bot = MEVBot::new(
         "wss://mainnet.infura.io/ws/v3/YOUR_INFURA_KEY",
         "YOUR_PRIVATE_KEY",
+        &["https://relay.flashbots.net".to_string()],
+        "YOUR_SEARCHER_PRIVATE_KEY",
         U256::from(1_000_000_000_000_000_000u64), // 1 ETH minimum profit
     ).await?;
 
@@ -0,0 +1,152 @@
+use ethers::types::H256;
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::Mutex,
+};
+
+/// Default number of recently-seen pending tx hashes to remember before the
+/// oldest entries are evicted.
+const DEFAULT_SEEN_CAPACITY: usize = 50_000;
+
+/// Default number of self-submitted tx hashes to remember before the oldest
+/// entries are evicted. Smaller than `DEFAULT_SEEN_CAPACITY` since the bot's
+/// own in-flight bundles are a much smaller set than all pending txs seen.
+const DEFAULT_EXCLUDED_CAPACITY: usize = 10_000;
+
+/// Default number of pending txs fetched and analyzed concurrently.
+const DEFAULT_CONCURRENCY: usize = 16;
+
+/// A bounded, FIFO-eviction cache of tx hashes we've already looked at.
+/// Plain insert-or-skip is all `MempoolMonitor` needs (no re-access
+/// "touch" reordering), so this is simpler than a full LRU while giving the
+/// same bounded-memory guarantee.
+struct SeenCache {
+    capacity: usize,
+    set: HashSet<H256>,
+    order: VecDeque<H256>,
+}
+
+impl SeenCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            set: HashSet::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn contains(&self, tx_hash: &H256) -> bool {
+        self.set.contains(tx_hash)
+    }
+
+    fn insert(&mut self, tx_hash: H256) {
+        if !self.set.insert(tx_hash) {
+            return;
+        }
+        self.order.push_back(tx_hash);
+        if self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.set.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Tracks which pending transactions the bot has already evaluated or
+/// submitted itself, so `MEVBot` doesn't re-analyze the same tx twice or
+/// sandwich its own in-flight bundle legs when they echo back through the
+/// mempool.
+pub struct MempoolMonitor {
+    seen: Mutex<SeenCache>,
+    excluded: Mutex<SeenCache>,
+    concurrency: usize,
+}
+
+impl MempoolMonitor {
+    pub fn new() -> Self {
+        Self::with_limits(DEFAULT_SEEN_CAPACITY, DEFAULT_CONCURRENCY)
+    }
+
+    pub fn with_limits(seen_capacity: usize, concurrency: usize) -> Self {
+        Self {
+            seen: Mutex::new(SeenCache::new(seen_capacity)),
+            excluded: Mutex::new(SeenCache::new(DEFAULT_EXCLUDED_CAPACITY)),
+            concurrency: concurrency.max(1),
+        }
+    }
+
+    /// How many pending txs should be fetched and analyzed in parallel.
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// Registers hashes the bot itself submitted, so they're filtered out if
+    /// they reappear in the pending tx stream instead of being re-analyzed
+    /// as someone else's opportunity.
+    pub fn exclude_own(&self, tx_hashes: &[H256]) {
+        let mut excluded = self.excluded.lock().unwrap();
+        for tx_hash in tx_hashes {
+            excluded.insert(*tx_hash);
+        }
+    }
+
+    /// Returns `true` if `tx_hash` hasn't been seen before and isn't one of
+    /// the bot's own submissions, and marks it seen, so a second call with
+    /// the same hash returns `false` while it is still in the bounded seen
+    /// cache. Excluded hashes are rejected without being marked seen.
+    pub fn should_process(&self, tx_hash: H256) -> bool {
+        if self.excluded.lock().unwrap().contains(&tx_hash) {
+            return false;
+        }
+
+        let mut seen = self.seen.lock().unwrap();
+        if seen.contains(&tx_hash) {
+            return false;
+        }
+        seen.insert(tx_hash);
+        true
+    }
+}
+
+impl Default for MempoolMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_process_only_once_per_hash() {
+        let monitor = MempoolMonitor::with_limits(10, 1);
+        let tx_hash = H256::random();
+
+        assert!(monitor.should_process(tx_hash));
+        assert!(!monitor.should_process(tx_hash));
+    }
+
+    #[test]
+    fn exclude_own_filters_future_processing() {
+        let monitor = MempoolMonitor::with_limits(10, 1);
+        let tx_hash = H256::random();
+
+        monitor.exclude_own(&[tx_hash]);
+        assert!(!monitor.should_process(tx_hash));
+    }
+
+    #[test]
+    fn seen_cache_evicts_the_oldest_entry_past_capacity() {
+        let mut cache = SeenCache::new(2);
+        let (a, b, c) = (H256::random(), H256::random(), H256::random());
+
+        cache.insert(a);
+        cache.insert(b);
+        cache.insert(c);
+
+        assert!(!cache.contains(&a));
+        assert!(cache.contains(&b));
+        assert!(cache.contains(&c));
+    }
+}
@@ -0,0 +1,290 @@
+use crate::bundle_relay::BundleLeg;
+use ethers::{
+    providers::Middleware,
+    types::{
+        transaction::eip2718::TypedTransaction, Address, NameOrAddress, TransactionRequest, H256,
+        U256,
+    },
+};
+use revm::{
+    db::{CacheDB, Database, DatabaseRef},
+    primitives::{
+        AccountInfo, Address as RAddress, Bytecode, ExecutionResult, ResultAndState, TransactTo,
+        TxEnv, B256, U256 as RU256,
+    },
+    EVM,
+};
+use std::sync::Arc;
+use anyhow::{anyhow, Result};
+
+const BALANCE_OF_SELECTOR: [u8; 4] = [0x70, 0xa0, 0x82, 0x31];
+
+// 没有设置gas price的自有交易腿按这个价格计gas，保证原生余额确实扣掉了gas
+const DEFAULT_GAS_PRICE_GWEI: u64 = 20;
+
+#[derive(Debug, Clone, Copy)]
+pub struct SimulationOutcome {
+    pub profit: U256,
+    pub gas_used: U256,
+}
+
+/// Forks current chain state for a single simulation by lazily pulling
+/// account info, storage, and code from a live node through `Middleware`,
+/// rather than requiring a synced archive node. Wrapped in `CacheDB`, so a
+/// front-run/victim/back-run sequence that touches the same pool reserves
+/// multiple times only pays for one round-trip per slot.
+struct RemoteDb<M: Middleware> {
+    provider: Arc<M>,
+    runtime: tokio::runtime::Handle,
+}
+
+impl<M: Middleware> RemoteDb<M> {
+    fn new(provider: Arc<M>) -> Self {
+        Self {
+            provider,
+            runtime: tokio::runtime::Handle::current(),
+        }
+    }
+
+    fn block_on<F: std::future::Future>(&self, fut: F) -> F::Output {
+        tokio::task::block_in_place(|| self.runtime.block_on(fut))
+    }
+}
+
+// CacheDB<ExtDB> requires ExtDB: DatabaseRef (an immutable-reference-only
+// backing store); RemoteDb only ever reads through the provider, so it
+// implements that rather than the mutable Database.
+impl<M: Middleware> DatabaseRef for RemoteDb<M> {
+    type Error = anyhow::Error;
+
+    fn basic(&self, address: RAddress) -> Result<Option<AccountInfo>, Self::Error> {
+        let address = Address::from_slice(address.as_slice());
+        let provider = self.provider.clone();
+
+        let (balance, nonce, code) = self.block_on(async move {
+            let balance = provider
+                .get_balance(address, None)
+                .await
+                .map_err(|e| anyhow!("get_balance failed: {e}"))?;
+            let nonce = provider
+                .get_transaction_count(address, None)
+                .await
+                .map_err(|e| anyhow!("get_transaction_count failed: {e}"))?;
+            let code = provider
+                .get_code(address, None)
+                .await
+                .map_err(|e| anyhow!("get_code failed: {e}"))?;
+            Ok::<_, anyhow::Error>((balance, nonce, code))
+        })?;
+
+        Ok(Some(AccountInfo {
+            balance: RU256::from_limbs(balance.0),
+            nonce: nonce.as_u64(),
+            code_hash: B256::from_slice(&ethers::utils::keccak256(&code)),
+            code: (!code.is_empty()).then(|| Bytecode::new_raw(code.0.into())),
+        }))
+    }
+
+    fn code_by_hash(&self, _code_hash: B256) -> Result<Bytecode, Self::Error> {
+        // 只通过basic()按地址回源取code，不支持仅凭code hash反查
+        Err(anyhow!("code_by_hash is unsupported; RemoteDb resolves code via basic()"))
+    }
+
+    fn storage(&self, address: RAddress, index: RU256) -> Result<RU256, Self::Error> {
+        let address = Address::from_slice(address.as_slice());
+        let slot = H256::from_slice(&index.to_be_bytes::<32>());
+        let provider = self.provider.clone();
+
+        let value = self
+            .block_on(async move { provider.get_storage_at(address, slot, None).await })
+            .map_err(|e| anyhow!("get_storage_at failed: {e}"))?;
+        Ok(RU256::from_be_bytes(value.0))
+    }
+
+    fn block_hash(&self, number: RU256) -> Result<B256, Self::Error> {
+        let number = number.to::<u64>();
+        let provider = self.provider.clone();
+        let block = self
+            .block_on(async move { provider.get_block(number).await })
+            .map_err(|e| anyhow!("get_block failed: {e}"))?
+            .ok_or_else(|| anyhow!("block {} not found while simulating", number))?;
+        Ok(B256::from_slice(block.hash.unwrap_or_default().as_bytes()))
+    }
+}
+
+/// Runs candidate bundle legs against a local fork of current chain state so
+/// `MEVBot` can price the true post-execution effect of a trade before
+/// submitting anything, instead of guessing at a profit.
+pub struct Simulator<M: Middleware> {
+    provider: Arc<M>,
+}
+
+impl<M: Middleware + 'static> Simulator<M> {
+    pub fn new(provider: Arc<M>) -> Self {
+        Self { provider }
+    }
+
+    /// Executes `legs` in order (e.g. front-run, victim, back-run) against a
+    /// fresh fork of current state and returns `beneficiary`'s net gain.
+    /// Profit is measured in `profit_token`'s ERC-20 balance if given, or in
+    /// native balance otherwise; only the native measure has the gas paid by
+    /// `beneficiary`'s legs taken out of it, and the ERC-20 measure does not
+    /// deduct gas (the total is reported separately as `gas_used`). Returns
+    /// `None` if any leg reverts/halts, or if the sequence isn't actually
+    /// profitable.
+    pub async fn simulate_sequence(
+        &self,
+        legs: &[BundleLeg],
+        beneficiary: Address,
+        profit_token: Option<Address>,
+    ) -> Result<Option<SimulationOutcome>> {
+        let provider = self.provider.clone();
+        let legs = legs.to_vec();
+
+        tokio::task::spawn_blocking(move || {
+            let remote = RemoteDb::new(provider);
+            let db = CacheDB::new(remote);
+            let mut evm = EVM::new();
+            evm.database(db);
+
+            let beneficiary = to_revm_address(beneficiary);
+            let profit_token = profit_token.map(to_revm_address);
+
+            let starting_balance = read_balance(&mut evm, beneficiary, profit_token)?;
+
+            let mut gas_used = 0u64;
+            for leg in &legs {
+                evm.env.tx = leg_to_tx_env(leg)?;
+
+                let result = evm
+                    .transact_commit()
+                    .map_err(|e| anyhow!("simulation failed: {e:?}"))?;
+
+                match result {
+                    ExecutionResult::Success { gas_used: used, .. } => gas_used += used,
+                    ExecutionResult::Revert { .. } | ExecutionResult::Halt { .. } => return Ok(None),
+                }
+            }
+
+            let ending_balance = read_balance(&mut evm, beneficiary, profit_token)?;
+
+            if ending_balance <= starting_balance {
+                return Ok(None);
+            }
+
+            Ok(Some(SimulationOutcome {
+                profit: u256_from_revm(ending_balance - starting_balance),
+                gas_used: U256::from(gas_used),
+            }))
+        })
+        .await?
+    }
+}
+
+type Db<M> = CacheDB<RemoteDb<M>>;
+
+fn read_balance<M: Middleware>(
+    evm: &mut EVM<Db<M>>,
+    holder: RAddress,
+    token: Option<RAddress>,
+) -> Result<RU256> {
+    match token {
+        None => Ok(evm.db.as_mut().unwrap().basic(holder)?.map(|acc| acc.balance).unwrap_or_default()),
+        Some(token) => token_balance(evm, token, holder),
+    }
+}
+
+// Reads an ERC-20 balance via a read-only balanceOf call instead of a raw
+// storage slot, since the mapping slot layout isn't the same across tokens.
+fn token_balance<M: Middleware>(
+    evm: &mut EVM<Db<M>>,
+    token: RAddress,
+    holder: RAddress,
+) -> Result<RU256> {
+    let mut calldata = BALANCE_OF_SELECTOR.to_vec();
+    calldata.extend_from_slice(&[0u8; 12]);
+    calldata.extend_from_slice(holder.as_slice());
+
+    evm.env.tx = TxEnv {
+        caller: holder,
+        transact_to: TransactTo::Call(token),
+        data: calldata.into(),
+        gas_limit: 1_000_000,
+        ..Default::default()
+    };
+
+    let ResultAndState { result, .. } =
+        evm.transact().map_err(|e| anyhow!("balanceOf simulation failed: {e:?}"))?;
+    let output = result
+        .into_output()
+        .ok_or_else(|| anyhow!("balanceOf call produced no output"))?;
+    if output.len() < 32 {
+        return Err(anyhow!("malformed balanceOf response"));
+    }
+
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&output[output.len() - 32..]);
+    Ok(RU256::from_be_bytes(bytes))
+}
+
+fn leg_to_tx_env(leg: &BundleLeg) -> Result<TxEnv> {
+    match leg {
+        BundleLeg::Owned(tx) => Ok(to_tx_env(tx)),
+        BundleLeg::Raw(raw) => raw_tx_to_tx_env(raw),
+    }
+}
+
+// The victim's leg arrives as already-signed raw bytes (see BundleLeg::Raw),
+// so simulating it means decoding the signature back into a sender rather
+// than reading a `from` field that was never populated.
+fn raw_tx_to_tx_env(raw: &ethers::types::Bytes) -> Result<TxEnv> {
+    let rlp = rlp::Rlp::new(raw.as_ref());
+    let (typed_tx, signature) = TypedTransaction::decode_signed(&rlp)
+        .map_err(|e| anyhow!("failed to decode raw bundle leg: {e}"))?;
+    let sender = signature
+        .recover(typed_tx.sighash())
+        .map_err(|e| anyhow!("failed to recover raw bundle leg sender: {e}"))?;
+
+    Ok(TxEnv {
+        caller: to_revm_address(sender),
+        transact_to: match typed_tx.to() {
+            Some(NameOrAddress::Address(to)) => TransactTo::Call(to_revm_address(*to)),
+            _ => TransactTo::create(),
+        },
+        value: typed_tx.value().map(|v| RU256::from_limbs(v.0)).unwrap_or_default(),
+        data: typed_tx.data().cloned().unwrap_or_default().0.into(),
+        gas_limit: typed_tx.gas().map(|g| g.as_u64()).unwrap_or(5_000_000),
+        gas_price: typed_tx.gas_price().map(|p| RU256::from_limbs(p.0)).unwrap_or_default(),
+        ..Default::default()
+    })
+}
+
+fn to_revm_address(address: Address) -> RAddress {
+    RAddress::from_slice(address.as_bytes())
+}
+
+fn to_tx_env(tx: &TransactionRequest) -> TxEnv {
+    TxEnv {
+        caller: tx.from.map(to_revm_address).unwrap_or_default(),
+        transact_to: match tx.to {
+            Some(NameOrAddress::Address(to)) => TransactTo::Call(to_revm_address(to)),
+            _ => TransactTo::create(),
+        },
+        value: tx.value.map(|v| RU256::from_limbs(v.0)).unwrap_or_default(),
+        data: tx.data.clone().unwrap_or_default().0.into(),
+        gas_limit: tx.gas.map(|g| g.as_u64()).unwrap_or(5_000_000),
+        gas_price: tx
+            .gas_price
+            .map(|p| RU256::from_limbs(p.0))
+            .unwrap_or_else(default_gas_price),
+        ..Default::default()
+    }
+}
+
+fn default_gas_price() -> RU256 {
+    RU256::from(DEFAULT_GAS_PRICE_GWEI) * RU256::from(1_000_000_000u64)
+}
+
+fn u256_from_revm(value: RU256) -> U256 {
+    U256::from_little_endian(&value.to_le_bytes::<32>())
+}
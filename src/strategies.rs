@@ -0,0 +1,204 @@
+use crate::{
+    bundle_relay::BundleLeg, dex, ExecutorMiddleware, MEVOpportunity, OpportunityFinder,
+    OpportunityType, TransactionExecutor,
+};
+use crate::strategy::{Strategy, TxContext};
+use async_trait::async_trait;
+use ethers::{providers::Ws, types::{Address, Transaction, TransactionRequest, U256}};
+use std::sync::Arc;
+use anyhow::Result;
+
+/// Detects profitable sandwiches against pending DEX swaps and simulates
+/// them before returning an opportunity, so a bad quadratic solve or a
+/// reverting leg never reaches the executor.
+pub struct SandwichStrategy {
+    provider: Arc<ethers::providers::Provider<Ws>>,
+    simulator: Arc<crate::simulator::Simulator<ExecutorMiddleware>>,
+    executor: Arc<TransactionExecutor>,
+}
+
+impl SandwichStrategy {
+    pub fn new(
+        provider: Arc<ethers::providers::Provider<Ws>>,
+        simulator: Arc<crate::simulator::Simulator<ExecutorMiddleware>>,
+        executor: Arc<TransactionExecutor>,
+    ) -> Self {
+        Self {
+            provider,
+            simulator,
+            executor,
+        }
+    }
+
+    /// Builds the full sandwich bundle: a front-run (buy
+    /// `plan.front_run_amount_in` of `path[0]`), the victim's own pending
+    /// transaction relayed verbatim so it actually executes against the
+    /// moved reserves, and a back-run (sell everything the front-run
+    /// acquired, along the reverse path).
+    fn build_sandwich_transactions(
+        &self,
+        router: Address,
+        path: &[Address],
+        plan: &dex::SandwichPlan,
+        victim: &Transaction,
+    ) -> Result<Vec<BundleLeg>> {
+        let beneficiary = self.executor.address();
+        let deadline = U256::from(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)?
+                .as_secs()
+                + 120,
+        );
+
+        let front_run_data = dex::encode_swap_exact_tokens_for_tokens(
+            plan.front_run_amount_in,
+            U256::zero(),
+            path,
+            beneficiary,
+            deadline,
+        );
+        let front_run = TransactionRequest::new().from(beneficiary).to(router).data(front_run_data);
+
+        let back_path: Vec<Address> = path.iter().rev().copied().collect();
+        let back_run_data = dex::encode_swap_exact_tokens_for_tokens(
+            plan.front_run_amount_out,
+            U256::zero(),
+            &back_path,
+            beneficiary,
+            deadline,
+        );
+        let back_run = TransactionRequest::new().from(beneficiary).to(router).data(back_run_data);
+
+        Ok(vec![
+            BundleLeg::Owned(Box::new(front_run)),
+            BundleLeg::Raw(victim.rlp()),
+            BundleLeg::Owned(Box::new(back_run)),
+        ])
+    }
+}
+
+#[async_trait]
+impl Strategy for SandwichStrategy {
+    fn name(&self) -> &str {
+        "sandwich"
+    }
+
+    async fn evaluate(&self, ctx: &TxContext) -> Result<Vec<MEVOpportunity>> {
+        let TxContext::PendingTransaction(tx) = ctx else {
+            return Ok(vec![]);
+        };
+
+        // 检查是否是DEX交易
+        let Some(router) = tx.to else { return Ok(vec![]) };
+        if !dex::is_known_dex_router(router) {
+            return Ok(vec![]);
+        }
+
+        // 解析swap参数：amountIn、amountOutMin和交易路径
+        let Some(swap) = dex::decode_swap_calldata(&tx.input, tx.value)? else {
+            return Ok(vec![]);
+        };
+        if swap.path.len() < 2 {
+            return Ok(vec![]);
+        }
+
+        let token_in = swap.path[0];
+        let token_out = swap.path[swap.path.len() - 1];
+
+        let Some((reserve_in, reserve_out)) =
+            dex::get_reserves(self.provider.as_ref(), token_in, token_out).await?
+        else {
+            return Ok(vec![]);
+        };
+
+        // 根据受害者的amountOutMin约束求出利润最大化的前置买入量
+        let Some(plan) = dex::plan_sandwich(reserve_in, reserve_out, &swap) else {
+            return Ok(vec![]);
+        };
+
+        let transaction_data = self.build_sandwich_transactions(router, &swap.path, &plan, tx)?;
+
+        let outcome = self
+            .simulator
+            .simulate_sequence(&transaction_data, self.executor.address(), Some(token_in))
+            .await?;
+
+        // 模拟结果为None，说明序列中有一笔revert了，或者根本不赚钱
+        let Some(outcome) = outcome else {
+            return Ok(vec![]);
+        };
+
+        Ok(vec![MEVOpportunity {
+            opportunity_type: OpportunityType::Sandwich {
+                target_tx: tx.hash,
+                token: token_in,
+                amount: swap.amount_in,
+            },
+            profit_estimate: outcome.profit,
+            gas_estimate: outcome.gas_used,
+            block_number: tx.block_number.unwrap_or_default().as_u64(),
+            transaction_data,
+        }])
+    }
+
+    async fn build(&self, opportunity: &MEVOpportunity) -> Result<Vec<BundleLeg>> {
+        Ok(opportunity.transaction_data.clone())
+    }
+}
+
+/// Liquidation detection. Currently a stub: finding liquidatable positions
+/// needs monitoring the health factor of every position across whichever
+/// lending protocols this bot watches, which isn't wired up yet.
+pub struct LiquidationStrategy;
+
+#[async_trait]
+impl Strategy for LiquidationStrategy {
+    fn name(&self) -> &str {
+        "liquidation"
+    }
+
+    async fn evaluate(&self, _ctx: &TxContext) -> Result<Vec<MEVOpportunity>> {
+        // 检查是否有清算机会
+        // 这需要监控各种DeFi协议的健康度
+        Ok(vec![])
+    }
+
+    async fn build(&self, opportunity: &MEVOpportunity) -> Result<Vec<BundleLeg>> {
+        Ok(opportunity.transaction_data.clone())
+    }
+}
+
+/// Cross-DEX arbitrage, both the periodic price-difference scan and
+/// whatever a newly-mined block's state changes might open up.
+pub struct ArbitrageStrategy {
+    opportunity_finder: Arc<OpportunityFinder>,
+}
+
+impl ArbitrageStrategy {
+    pub fn new(opportunity_finder: Arc<OpportunityFinder>) -> Self {
+        Self { opportunity_finder }
+    }
+}
+
+#[async_trait]
+impl Strategy for ArbitrageStrategy {
+    fn name(&self) -> &str {
+        "arbitrage"
+    }
+
+    async fn evaluate(&self, ctx: &TxContext) -> Result<Vec<MEVOpportunity>> {
+        let opportunities = match ctx {
+            // 检查不同DEX之间的价格差异
+            TxContext::Tick => self.opportunity_finder.find_arbitrage_opportunities().await?,
+            // 分析新区块中的机会
+            TxContext::Block(block) => self.opportunity_finder.find_block_opportunities(block).await?,
+            TxContext::PendingTransaction(_) => None,
+        };
+
+        Ok(opportunities.unwrap_or_default())
+    }
+
+    async fn build(&self, opportunity: &MEVOpportunity) -> Result<Vec<BundleLeg>> {
+        Ok(opportunity.transaction_data.clone())
+    }
+}
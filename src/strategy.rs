@@ -0,0 +1,39 @@
+use crate::bundle_relay::BundleLeg;
+use crate::MEVOpportunity;
+use async_trait::async_trait;
+use ethers::types::{Block, Transaction, H256};
+use anyhow::Result;
+
+/// The event a `Strategy` is asked to look at: a newly-seen pending
+/// transaction, a newly-mined block, or a periodic tick for strategies that
+/// scan on a timer rather than react to a specific tx/block (e.g. cross-DEX
+/// arbitrage).
+pub enum TxContext {
+    PendingTransaction(Box<Transaction>),
+    Block(Box<Block<H256>>),
+    Tick,
+}
+
+/// A pluggable MEV opportunity type. Each strategy owns whatever provider/
+/// simulator handles it needs and is registered once on `MEVBot`, whose
+/// monitoring loops hand every pending tx, block and tick to all registered
+/// strategies, so a new opportunity category (JIT liquidity, backrun-only
+/// arbitrage, oracle-update liquidations, ...) needs no change to those
+/// loops. It does still need its own `OpportunityType` variant and an arm
+/// in the `match` of `TransactionExecutor::execute_opportunity`.
+#[async_trait]
+pub trait Strategy: Send + Sync {
+    /// Short name used in logs to say which strategy found/built something.
+    fn name(&self) -> &str;
+
+    /// Looks for opportunities of this strategy's kind in `ctx`. Strategies
+    /// that don't care about a given `TxContext` variant should just return
+    /// an empty vec rather than erroring.
+    async fn evaluate(&self, ctx: &TxContext) -> Result<Vec<MEVOpportunity>>;
+
+    /// Turns a previously-evaluated opportunity into the concrete
+    /// transactions to submit. Most strategies build `transaction_data`
+    /// eagerly inside `evaluate` so it can be priced by simulation before
+    /// being returned, in which case this is just a passthrough.
+    async fn build(&self, opportunity: &MEVOpportunity) -> Result<Vec<BundleLeg>>;
+}